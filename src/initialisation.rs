@@ -1,7 +1,7 @@
 use ndarray::{Array, Axis};
 use rand::{Rng, SeedableRng, rng};
 
-use crate::{Error, Float, TwoDee};
+use crate::{Error, Float, OneDee, TwoDee};
 
 /// returns `amount_of_particles` spaced around in a box with side lengths `box_size` in an fcc
 /// lattice structure.
@@ -42,8 +42,10 @@ pub(super) fn initial_positions(
     Ok(positions)
 }
 
+/// Draws a Maxwell-Boltzmann velocity for each particle from `Normal(0, sqrt(T/m_i))`, so heavier
+/// species start out moving more slowly than lighter ones at the same temperature.
 pub(super) fn initial_velocities(
-    amount_of_particles: usize,
+    masses: &OneDee,
     temperature: Float,
     seed: Option<u64>,
 ) -> Result<TwoDee, Error> {
@@ -52,14 +54,14 @@ pub(super) fn initial_velocities(
         None => rand::rngs::SmallRng::try_from_os_rng()?,
     };
 
-    let distribution = rand_distr::Normal::new(0.0, temperature.sqrt())?;
-    let mut velocities = Array::from_shape_vec(
-        (amount_of_particles, 3),
-        (&mut generator)
-            .sample_iter(distribution)
-            .take(amount_of_particles * 3)
-            .collect(),
-    )?;
+    let amount_of_particles = masses.len();
+    let mut velocities = Array::zeros((amount_of_particles, 3));
+    for (i, &mass) in masses.iter().enumerate() {
+        let distribution = rand_distr::Normal::new(0.0, (temperature / mass).sqrt())?;
+        for d in 0..3 {
+            velocities[[i, d]] = generator.sample(distribution);
+        }
+    }
     assert_eq!(velocities.shape()[0], amount_of_particles);
     velocities -= &velocities
         .mean_axis(Axis(0))
@@ -1,46 +1,151 @@
+use std::collections::VecDeque;
+
 use indicatif::{ProgressBar, ProgressStyle};
-use ndarray::{Array, Axis, Dimension};
+use ndarray::{Array, Axis};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+use rand_distr::StandardNormal;
 
 use crate::{
-    Error, Float, OneDee, ThreeDee, TwoDee,
-    physics::{atomic_distances, kinetic_energy, lj_force, potential_energy, temperature},
+    Error, Float, OneDee, TwoDee,
+    neighbors::NeighborList,
+    output::TrajectoryWriter,
+    physics::{
+        atomic_distances, atomic_distances_pairs, kinetic_energy, lj_force, lj_force_pairs,
+        potential_energy, temperature, wrap_into_box,
+    },
+    thermostat::Thermostat,
 };
 
+/// Per-particle mass, per-pair Lorentz–Berthelot-mixed Lennard-Jones parameters and the periodic
+/// box size, as produced by [`crate::species::SpeciesTable`].
+pub struct SystemParameters {
+    pub masses: OneDee,
+    pub sigma: TwoDee,
+    pub epsilon: TwoDee,
+    pub box_dim: Float,
+}
+
+/// How large a step to take (or start from, for [`Integrator::simulate_adaptive`]) and how long
+/// to run, shared across every [`Integrator::simulate*`] variant.
+#[derive(Clone, Copy)]
+pub struct RunConfig {
+    pub time_step_size: Float,
+    pub max_time: Float,
+}
+
 pub struct IntegrationStepResult {
     positions: TwoDee,
     velocities: TwoDee,
     forces: TwoDee,
-    force_magnitudes: TwoDee,
-    distances: TwoDee,
+    potential_energy: Float,
+    virial: Float,
 }
 
-#[derive(Debug)]
+/// The recorded trajectory of a simulation. Frames are appended one at a time as they are
+/// produced, so a fixed-step run and an adaptive-step run (whose frame count and frame spacing
+/// aren't known up front) can share the same result type.
+#[derive(Debug, Default)]
 pub struct IntegrationResult {
-    positions: ThreeDee,
-    velocities: ThreeDee,
-    virials: OneDee,
-    kinetic_energies: OneDee,
-    potential_energies: OneDee,
+    pub times: Vec<Float>,
+    pub positions: Vec<TwoDee>,
+    pub velocities: Vec<TwoDee>,
+    pub virials: Vec<Float>,
+    pub kinetic_energies: Vec<Float>,
+    pub potential_energies: Vec<Float>,
+    /// The step at which [`Integrator::simulate_nvt`] detected the system had reached its
+    /// target temperature and switched from thermostatted equilibration to NVE production.
+    /// `None` for runs that never equilibrated, or that never ran a thermostat at all.
+    pub equilibrium_timestep: Option<usize>,
+}
+
+impl IntegrationResult {
+    fn with_capacity(capacity: usize) -> Self {
+        IntegrationResult {
+            times: Vec::with_capacity(capacity),
+            positions: Vec::with_capacity(capacity),
+            velocities: Vec::with_capacity(capacity),
+            virials: Vec::with_capacity(capacity),
+            kinetic_energies: Vec::with_capacity(capacity),
+            potential_energies: Vec::with_capacity(capacity),
+            equilibrium_timestep: None,
+        }
+    }
+}
+
+/// Equilibrium-detection parameters for [`Integrator::simulate_nvt`]: equilibrium is declared
+/// once the running average of the instantaneous temperature over `window` consecutive steps
+/// stays within `tolerance` of the thermostat's target temperature.
+pub struct EquilibrationConfig {
+    pub window: usize,
+    pub tolerance: Float,
+}
+
+/// Local error tolerance and step-rescaling parameters for [`Integrator::simulate_adaptive`].
+pub struct AdaptiveConfig {
+    /// Maximum acceptable max-norm error between a full step and two half steps.
+    pub tolerance: Float,
+    /// Keeps the step from shrinking or growing too aggressively in a single rescale.
+    pub min_factor: Float,
+    pub max_factor: Float,
+    /// Safety margin applied to the step-doubling error estimator, `~0.9` is standard.
+    pub safety: Float,
+    /// If set, every recorded frame lands on a multiple of this time interval: `dt` is capped
+    /// (in addition to the error-driven rescale) so a step never overshoots the next one. `None`
+    /// records a frame at every accepted step instead, at whatever time it lands on.
+    pub sample_interval: Option<Float>,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        AdaptiveConfig {
+            tolerance: 1e-4,
+            min_factor: 0.2,
+            max_factor: 5.0,
+            safety: 0.9,
+            sample_interval: None,
+        }
+    }
 }
 
+/// Order of the velocity-Verlet local truncation error, used by the step-doubling rescale rule.
+const VELOCITY_VERLET_ORDER: Float = 2.0;
+
 pub trait Integrator {
     fn initialisation(&mut self) -> Result<(), Error>;
     fn deinit(&mut self) -> Result<(), Error>;
+
+    /// Evaluates the net per-particle force, total potential energy and virial at `positions`.
+    /// Defaults to the dense O(N²) pair evaluation; override to substitute e.g. a
+    /// [`NeighborList`] for roughly O(N) cost at large particle counts (see
+    /// [`VerletNeighborList`]).
+    fn evaluate(
+        &mut self,
+        positions: &TwoDee,
+        parameters: &SystemParameters,
+    ) -> Result<(TwoDee, Float, Float), Error> {
+        let (relative_positions, distances) = atomic_distances(positions, parameters.box_dim)?;
+        let (force_magnitudes, forces) =
+            lj_force(relative_positions, &distances, &parameters.sigma, &parameters.epsilon);
+        let potential = potential_energy(&distances, &parameters.sigma, &parameters.epsilon);
+        let virial = 0.5 * (&distances * &force_magnitudes).sum();
+        Ok((forces, potential, virial))
+    }
+
     fn integration_step(
         &mut self,
         positions: TwoDee,
         velocities: TwoDee,
         forces: TwoDee,
         time_step_size: Float,
-        box_dim: Float,
+        parameters: &SystemParameters,
     ) -> Result<IntegrationStepResult, Error>;
+
     fn simulate(
         &mut self,
         initial_positions: TwoDee,
         initial_velocities: TwoDee,
-        time_step_size: Float,
-        max_time: Float,
-        box_dim: Float,
+        run_config: RunConfig,
+        parameters: &SystemParameters,
     ) -> Result<IntegrationResult, Error> {
         assert_eq!(
             initial_positions.shape()[0],
@@ -48,28 +153,17 @@ pub trait Integrator {
             "positions and velocities contain a different amount of particles"
         );
         assert!(
-            time_step_size < max_time,
+            run_config.time_step_size < run_config.max_time,
             "time step size larger then max time"
         );
-        let amount_of_particles = initial_positions.shape()[0];
-        let timesteps = (max_time / time_step_size) as usize;
-        let r_max = (box_dim.powi(2) * 3.0).sqrt();
+        let timesteps = (run_config.max_time / run_config.time_step_size) as usize;
 
-        let mut positions = Array::zeros((amount_of_particles, 3, timesteps));
-        let mut velocities = Array::zeros((amount_of_particles, 3, timesteps));
-
-        let mut kinetic_energies = Array::zeros(timesteps);
-        let mut potential_energies = Array::zeros(timesteps);
-        let mut virials = Array::zeros(timesteps);
-        let mut temperatures = Array::zeros(timesteps);
-        let mut equilibrium_timestep: Option<usize> = None;
+        let mut result = IntegrationResult::with_capacity(timesteps);
 
         self.initialisation()?;
         let mut current_positions = initial_positions;
         let mut current_velocities = initial_velocities;
-        let (mut relative_positions, mut distances) =
-            atomic_distances(&current_positions, box_dim)?;
-        let (mut force_magnitudes, mut forces) = lj_force(relative_positions, &distances);
+        let (mut forces, mut potential, mut virial) = self.evaluate(&current_positions, parameters)?;
         let bar = ProgressBar::new(timesteps as u64).with_style(
             ProgressStyle::default_bar()
                 .template("[{elapsed_precise}/{duration_precise} (Remaining: {eta_precise})] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
@@ -78,48 +172,323 @@ pub trait Integrator {
         );
         for step in 0..timesteps {
             // start by assigning all the stuff
-            positions
-                .index_axis_mut(Axis(2), step)
-                .assign(&current_positions);
-            velocities
-                .index_axis_mut(Axis(2), step)
-                .assign(&current_velocities);
-
-            kinetic_energies[step] = kinetic_energy(&current_positions);
-            potential_energies[step] = potential_energy(&distances);
-            virials[step] = 0.5 * (distances * force_magnitudes).sum();
-            temperatures[step] = temperature(kinetic_energies[step], amount_of_particles);
+            result.times.push(step as Float * run_config.time_step_size);
+            result.positions.push(current_positions.clone());
+            result.velocities.push(current_velocities.clone());
+
+            result
+                .kinetic_energies
+                .push(kinetic_energy(&current_velocities, &parameters.masses));
+            result.potential_energies.push(potential);
+            result.virials.push(virial);
 
             // I can move them here, this will give new ones
             let IntegrationStepResult {
                 positions: new_positions,
                 velocities: new_velocities,
                 forces: new_forces,
-                force_magnitudes: new_magnitudes,
-                distances: new_distances,
+                potential_energy: new_potential,
+                virial: new_virial,
             } = self.integration_step(
                 current_positions,
                 current_velocities,
                 forces,
-                time_step_size,
-                box_dim,
+                run_config.time_step_size,
+                parameters,
             )?;
 
             bar.inc(1);
             current_positions = new_positions;
             current_velocities = new_velocities;
             forces = new_forces;
-            force_magnitudes = new_magnitudes;
-            distances = new_distances;
+            potential = new_potential;
+            virial = new_virial;
         }
         bar.finish_with_message("Finished simulation");
-        Ok(IntegrationResult {
-            positions,
-            velocities,
-            virials,
-            kinetic_energies,
-            potential_energies,
-        })
+        Ok(result)
+    }
+
+    /// Adaptive-step variant of [`Integrator::simulate`] using step-doubling error control: each
+    /// candidate step of size `dt` is taken once at full size and twice at `dt/2`, the max-norm
+    /// difference between the two results estimates the local error, and the step is accepted
+    /// (keeping the more accurate half-step result) or rejected and retried at a smaller `dt`
+    /// depending on how that error compares to `config.tolerance`. `dt` is rescaled after every
+    /// attempt, accepted or not, so the step size tracks how stiff the LJ force currently is
+    /// (e.g. during a close approach) instead of staying fixed for the whole run. `dt` is also
+    /// capped, independent of that rescale, so the run never steps past `run_config.max_time` or
+    /// (if `config.sample_interval` is set) past the next requested sampling time.
+    fn simulate_adaptive(
+        &mut self,
+        initial_positions: TwoDee,
+        initial_velocities: TwoDee,
+        run_config: RunConfig,
+        parameters: &SystemParameters,
+        config: AdaptiveConfig,
+    ) -> Result<IntegrationResult, Error> {
+        assert_eq!(
+            initial_positions.shape()[0],
+            initial_velocities.shape()[0],
+            "positions and velocities contain a different amount of particles"
+        );
+        assert!(
+            run_config.time_step_size < run_config.max_time,
+            "time step size larger then max time"
+        );
+        let estimated_frames = (run_config.max_time / run_config.time_step_size) as usize;
+        let mut result = IntegrationResult::with_capacity(estimated_frames);
+
+        self.initialisation()?;
+        let mut current_positions = initial_positions;
+        let mut current_velocities = initial_velocities;
+        let (mut forces, mut potential, mut virial) = self.evaluate(&current_positions, parameters)?;
+        let mut dt = run_config.time_step_size;
+        let mut time = 0.0;
+
+        let bar = ProgressBar::new(run_config.max_time.ceil() as u64).with_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] {bar:40.cyan/blue} t={msg}")
+                .unwrap()
+                .progress_chars("##-"),
+        );
+        while time < run_config.max_time {
+            dt = dt.min(run_config.max_time - time);
+            if let Some(sample_interval) = config.sample_interval {
+                let next_sample_time = ((time / sample_interval).floor() + 1.0) * sample_interval;
+                dt = dt.min(next_sample_time - time);
+            }
+
+            result.times.push(time);
+            result.positions.push(current_positions.clone());
+            result.velocities.push(current_velocities.clone());
+            result
+                .kinetic_energies
+                .push(kinetic_energy(&current_velocities, &parameters.masses));
+            result.potential_energies.push(potential);
+            result.virials.push(virial);
+
+            loop {
+                let full = self.integration_step(
+                    current_positions.clone(),
+                    current_velocities.clone(),
+                    forces.clone(),
+                    dt,
+                    parameters,
+                )?;
+                let half_step = self.integration_step(
+                    current_positions.clone(),
+                    current_velocities.clone(),
+                    forces.clone(),
+                    dt * 0.5,
+                    parameters,
+                )?;
+                let half = self.integration_step(
+                    half_step.positions,
+                    half_step.velocities,
+                    half_step.forces,
+                    dt * 0.5,
+                    parameters,
+                )?;
+
+                let error = (&full.positions - &half.positions)
+                    .mapv(Float::abs)
+                    .fold(0.0, |running_max: Float, &component| running_max.max(component));
+
+                let rescale = |err: Float| {
+                    (config.safety * (config.tolerance / err).powf(1.0 / (VELOCITY_VERLET_ORDER + 1.0)))
+                        .clamp(config.min_factor, config.max_factor)
+                };
+
+                if error <= config.tolerance
+                    || dt <= run_config.time_step_size * config.min_factor * config.min_factor
+                {
+                    time += dt;
+                    current_positions = half.positions;
+                    current_velocities = half.velocities;
+                    forces = half.forces;
+                    potential = half.potential_energy;
+                    virial = half.virial;
+                    dt *= rescale(error.max(Float::EPSILON));
+                    break;
+                }
+                dt *= rescale(error);
+            }
+
+            bar.set_position(time.min(run_config.max_time) as u64);
+            bar.set_message(format!("{time:.3}"));
+        }
+        bar.finish_with_message(format!("{time:.3}"));
+
+        Ok(result)
+    }
+
+    /// NVT variant of [`Integrator::simulate`]: runs `thermostat` every step to couple the
+    /// system to its target temperature, and tracks the running average of the instantaneous
+    /// temperature over `equilibration.window` steps. Once that average first falls within
+    /// `equilibration.tolerance` of the thermostat's target, the step is recorded as
+    /// [`IntegrationResult::equilibrium_timestep`] and the thermostat is switched off for the
+    /// rest of the run, leaving NVE production dynamics for everything after.
+    fn simulate_nvt(
+        &mut self,
+        initial_positions: TwoDee,
+        initial_velocities: TwoDee,
+        run_config: RunConfig,
+        parameters: &SystemParameters,
+        thermostat: &impl Thermostat,
+        equilibration: EquilibrationConfig,
+    ) -> Result<IntegrationResult, Error> {
+        assert_eq!(
+            initial_positions.shape()[0],
+            initial_velocities.shape()[0],
+            "positions and velocities contain a different amount of particles"
+        );
+        assert!(
+            run_config.time_step_size < run_config.max_time,
+            "time step size larger then max time"
+        );
+        let timesteps = (run_config.max_time / run_config.time_step_size) as usize;
+        let target_temperature = thermostat.target_temperature();
+        let mut recent_temperatures = VecDeque::with_capacity(equilibration.window);
+
+        let mut result = IntegrationResult::with_capacity(timesteps);
+
+        self.initialisation()?;
+        let mut current_positions = initial_positions;
+        let mut current_velocities = initial_velocities;
+        let (mut forces, mut potential, mut virial) = self.evaluate(&current_positions, parameters)?;
+        let bar = ProgressBar::new(timesteps as u64).with_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}/{duration_precise} (Remaining: {eta_precise})] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+                .unwrap()
+                .progress_chars("##-"),
+        );
+        for step in 0..timesteps {
+            result.times.push(step as Float * run_config.time_step_size);
+            result.positions.push(current_positions.clone());
+            result.velocities.push(current_velocities.clone());
+
+            let kinetic = kinetic_energy(&current_velocities, &parameters.masses);
+            result.kinetic_energies.push(kinetic);
+            result.potential_energies.push(potential);
+            result.virials.push(virial);
+
+            let current_temperature = temperature(kinetic, current_positions.shape()[0]);
+            if result.equilibrium_timestep.is_none() {
+                thermostat.apply(&mut current_velocities, current_temperature, run_config.time_step_size);
+
+                if recent_temperatures.len() == equilibration.window {
+                    recent_temperatures.pop_front();
+                }
+                recent_temperatures.push_back(current_temperature);
+                if recent_temperatures.len() == equilibration.window {
+                    let average =
+                        recent_temperatures.iter().sum::<Float>() / equilibration.window as Float;
+                    if (average - target_temperature).abs() <= equilibration.tolerance {
+                        result.equilibrium_timestep = Some(step);
+                    }
+                }
+            }
+
+            let IntegrationStepResult {
+                positions: new_positions,
+                velocities: new_velocities,
+                forces: new_forces,
+                potential_energy: new_potential,
+                virial: new_virial,
+            } = self.integration_step(
+                current_positions,
+                current_velocities,
+                forces,
+                run_config.time_step_size,
+                parameters,
+            )?;
+
+            bar.inc(1);
+            current_positions = new_positions;
+            current_velocities = new_velocities;
+            forces = new_forces;
+            potential = new_potential;
+            virial = new_virial;
+        }
+        bar.finish_with_message("Finished simulation");
+        Ok(result)
+    }
+
+    /// Streaming variant of [`Integrator::simulate`]: every `sample_every`-th step's frame is
+    /// handed straight to `writer` and then dropped, instead of being appended to an
+    /// [`IntegrationResult`]. That keeps memory use flat over the whole run, so it can go on for
+    /// far longer than the available RAM would let an in-memory result hold, and `writer` can
+    /// stream the trajectory straight to disk in a format standard MD tooling already reads.
+    fn simulate_streaming(
+        &mut self,
+        initial_positions: TwoDee,
+        initial_velocities: TwoDee,
+        run_config: RunConfig,
+        parameters: &SystemParameters,
+        sample_every: usize,
+        writer: &mut dyn TrajectoryWriter,
+    ) -> Result<(), Error> {
+        assert_eq!(
+            initial_positions.shape()[0],
+            initial_velocities.shape()[0],
+            "positions and velocities contain a different amount of particles"
+        );
+        assert!(
+            run_config.time_step_size < run_config.max_time,
+            "time step size larger then max time"
+        );
+        assert!(sample_every > 0, "sample_every must be at least 1");
+        let timesteps = (run_config.max_time / run_config.time_step_size) as usize;
+
+        self.initialisation()?;
+        let mut current_positions = initial_positions;
+        let mut current_velocities = initial_velocities;
+        let (mut forces, mut potential, mut virial) = self.evaluate(&current_positions, parameters)?;
+        let bar = ProgressBar::new(timesteps as u64).with_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}/{duration_precise} (Remaining: {eta_precise})] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+                .unwrap()
+                .progress_chars("##-"),
+        );
+        for step in 0..timesteps {
+            if step % sample_every == 0 {
+                let kinetic = kinetic_energy(&current_velocities, &parameters.masses);
+                let current_temperature = temperature(kinetic, current_positions.shape()[0]);
+                writer.write_frame(
+                    step,
+                    step as Float * run_config.time_step_size,
+                    parameters.box_dim,
+                    &current_positions,
+                    &current_velocities,
+                    kinetic,
+                    potential,
+                    virial,
+                    current_temperature,
+                )?;
+            }
+
+            let IntegrationStepResult {
+                positions: new_positions,
+                velocities: new_velocities,
+                forces: new_forces,
+                potential_energy: new_potential,
+                virial: new_virial,
+            } = self.integration_step(
+                current_positions,
+                current_velocities,
+                forces,
+                run_config.time_step_size,
+                parameters,
+            )?;
+
+            bar.inc(1);
+            current_positions = new_positions;
+            current_velocities = new_velocities;
+            forces = new_forces;
+            potential = new_potential;
+            virial = new_virial;
+        }
+        bar.finish_with_message("Finished simulation");
+        Ok(())
     }
 }
 
@@ -140,22 +509,24 @@ impl Integrator for Verlet {
         velocities: TwoDee,
         forces: TwoDee,
         time_step_size: Float,
-        box_dim: Float,
+        parameters: &SystemParameters,
     ) -> Result<IntegrationStepResult, Error> {
+        let masses = parameters.masses.view().insert_axis(Axis(1));
+        let acceleration = &forces / &masses;
         let new_positions = positions
             + &velocities * time_step_size
-            + &forces * time_step_size * time_step_size * 0.5;
-        let (relative_positions, new_distances) = atomic_distances(&new_positions, box_dim)?;
-        let (new_magnitudes, new_forces) = lj_force(relative_positions, &new_distances);
-        let dvel = (forces + &new_forces) * time_step_size * 0.5;
+            + &acceleration * time_step_size * time_step_size * 0.5;
+        let (new_forces, potential_energy, virial) = self.evaluate(&new_positions, parameters)?;
+        let new_acceleration = &new_forces / &masses;
+        let dvel = (acceleration + new_acceleration) * time_step_size * 0.5;
         let new_velocities = velocities + dvel;
 
         Ok(IntegrationStepResult {
             positions: new_positions,
             velocities: new_velocities,
             forces: new_forces,
-            force_magnitudes: new_magnitudes,
-            distances: new_distances,
+            potential_energy,
+            virial,
         })
     }
 }
@@ -178,22 +549,414 @@ impl Integrator for VerletCUDA {
         velocities: TwoDee,
         forces: TwoDee,
         time_step_size: Float,
-        box_dim: Float,
+        parameters: &SystemParameters,
     ) -> Result<IntegrationStepResult, Error> {
+        let masses = parameters.masses.view().insert_axis(Axis(1));
+        let acceleration = &forces / &masses;
         let new_positions = positions
             + &velocities * time_step_size
-            + &forces * time_step_size * time_step_size * 0.5;
-        let (relative_positions, new_distances) = atomic_distances(&new_positions, box_dim)?;
-        let (new_magnitudes, new_forces) = lj_force(relative_positions, &new_distances);
-        let dvel = (forces + &new_forces) * time_step_size * 0.5;
+            + &acceleration * time_step_size * time_step_size * 0.5;
+        let (new_forces, potential_energy, virial) = self.evaluate(&new_positions, parameters)?;
+        let new_acceleration = &new_forces / &masses;
+        let dvel = (acceleration + new_acceleration) * time_step_size * 0.5;
         let new_velocities = velocities + dvel;
 
         Ok(IntegrationStepResult {
             positions: new_positions,
             velocities: new_velocities,
             forces: new_forces,
-            force_magnitudes: new_magnitudes,
-            distances: new_distances,
+            potential_energy,
+            virial,
+        })
+    }
+}
+
+/// A BAOAB Langevin integrator: couples every particle to a stochastic thermal bath at
+/// `temperature` through a friction coefficient, giving canonical-ensemble sampling and
+/// genuinely diffusive dynamics that plain NVE velocity Verlet cannot provide.
+pub struct Langevin {
+    pub friction: Float,
+    pub temperature: Float,
+    rng: SmallRng,
+}
+
+impl Langevin {
+    /// Reuses the same seeded `SmallRng` pathway as
+    /// [`crate::initialisation::initial_velocities`], so a run stays reproducible via `SEED`.
+    pub fn new(friction: Float, temperature: Float, seed: Option<u64>) -> Result<Self, Error> {
+        let rng = match seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::try_from_os_rng()?,
+        };
+        Ok(Langevin {
+            friction,
+            temperature,
+            rng,
+        })
+    }
+}
+
+impl Integrator for Langevin {
+    fn initialisation(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn deinit(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// BAOAB splitting: half-kick (B), half-drift (A), Ornstein-Uhlenbeck bath coupling (O),
+    /// half-drift (A), force recompute, half-kick (B). The O step scales velocities by
+    /// `c = exp(-γ·dt)` and adds thermal noise `sqrt((1-c²)·T/m)·ξ` with a fresh standard-normal
+    /// `ξ` per particle, so between the two kicks the dynamics samples the canonical ensemble
+    /// instead of conserving energy exactly.
+    fn integration_step(
+        &mut self,
+        positions: TwoDee,
+        velocities: TwoDee,
+        forces: TwoDee,
+        time_step_size: Float,
+        parameters: &SystemParameters,
+    ) -> Result<IntegrationStepResult, Error> {
+        let amount_of_particles = positions.shape()[0];
+        let masses = parameters.masses.view().insert_axis(Axis(1));
+
+        // B: half-kick with the forces carried over from the previous step.
+        let velocities = velocities + (&forces / &masses) * (time_step_size * 0.5);
+
+        // A: half-drift, wrapped back into the periodic box.
+        let positions = wrap_into_box(
+            positions + &velocities * (time_step_size * 0.5),
+            parameters.box_dim,
+        );
+
+        // O: Ornstein-Uhlenbeck velocity update against the thermal bath.
+        let decay = (-self.friction * time_step_size).exp();
+        let noise_scale =
+            masses.mapv(|mass| ((1.0 - decay * decay) * self.temperature / mass).sqrt());
+        let noise: TwoDee = Array::from_shape_fn((amount_of_particles, 3), |_| {
+            self.rng.sample::<Float, _>(StandardNormal)
+        });
+        let velocities = velocities * decay + noise * &noise_scale;
+
+        // A: half-drift, wrapped back into the periodic box.
+        let new_positions = wrap_into_box(
+            positions + &velocities * (time_step_size * 0.5),
+            parameters.box_dim,
+        );
+
+        // recompute forces at the drifted positions.
+        let (new_forces, potential_energy, virial) = self.evaluate(&new_positions, parameters)?;
+
+        // B: final half-kick with the freshly computed forces.
+        let new_velocities = velocities + (&new_forces / &masses) * (time_step_size * 0.5);
+
+        Ok(IntegrationStepResult {
+            positions: new_positions,
+            velocities: new_velocities,
+            forces: new_forces,
+            potential_energy,
+            virial,
         })
     }
 }
+
+/// Velocity-Verlet integration backed by a [`NeighborList`] instead of the dense O(N²) pair
+/// evaluation every other integrator in this module uses, cutting the per-step cost to roughly
+/// O(N) for a roughly homogeneous density. The list is rebuilt lazily, only once
+/// [`NeighborList::needs_rebuild`] reports a particle could have drifted into or out of the
+/// cutoff shell. Uses the same per-pair `(σ_ij, ε_ij)` mixing matrices as the dense path, so it
+/// supports multi-species systems just like [`Verlet`] does.
+pub struct VerletNeighborList {
+    cutoff: Float,
+    skin: Float,
+    neighbor_list: Option<NeighborList>,
+}
+
+impl VerletNeighborList {
+    pub fn new(cutoff: Float, skin: Float) -> Self {
+        VerletNeighborList {
+            cutoff,
+            skin,
+            neighbor_list: None,
+        }
+    }
+}
+
+impl Integrator for VerletNeighborList {
+    fn initialisation(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn deinit(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn evaluate(
+        &mut self,
+        positions: &TwoDee,
+        parameters: &SystemParameters,
+    ) -> Result<(TwoDee, Float, Float), Error> {
+        let needs_rebuild = match &self.neighbor_list {
+            Some(list) => list.needs_rebuild(positions, parameters.box_dim),
+            None => true,
+        };
+        if needs_rebuild {
+            self.neighbor_list = Some(NeighborList::build(
+                positions,
+                parameters.box_dim,
+                self.cutoff,
+                self.skin,
+            ));
+        }
+        let pairs = &self
+            .neighbor_list
+            .as_ref()
+            .expect("just (re)built above")
+            .pairs;
+        let pair_distances = atomic_distances_pairs(positions, parameters.box_dim, pairs);
+        let amount_of_particles = positions.shape()[0];
+        let (forces, potential, virial) = lj_force_pairs(
+            amount_of_particles,
+            pairs,
+            &pair_distances,
+            &parameters.sigma,
+            &parameters.epsilon,
+            self.cutoff,
+        );
+        Ok((forces, potential, virial))
+    }
+
+    fn integration_step(
+        &mut self,
+        positions: TwoDee,
+        velocities: TwoDee,
+        forces: TwoDee,
+        time_step_size: Float,
+        parameters: &SystemParameters,
+    ) -> Result<IntegrationStepResult, Error> {
+        let masses = parameters.masses.view().insert_axis(Axis(1));
+        let acceleration = &forces / &masses;
+        let new_positions = positions
+            + &velocities * time_step_size
+            + &acceleration * time_step_size * time_step_size * 0.5;
+        let (new_forces, potential_energy, virial) = self.evaluate(&new_positions, parameters)?;
+        let new_acceleration = &new_forces / &masses;
+        let dvel = (acceleration + new_acceleration) * time_step_size * 0.5;
+        let new_velocities = velocities + dvel;
+
+        Ok(IntegrationStepResult {
+            positions: new_positions,
+            velocities: new_velocities,
+            forces: new_forces,
+            potential_energy,
+            virial,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::{Array, array};
+
+    use super::*;
+    use crate::thermostat::{Berendsen, VelocityRescaling};
+
+    /// With no interactions (ε = 0, so velocities are only ever touched by the thermostat) and a
+    /// [`VelocityRescaling`] thermostat, the very first rescale snaps the instantaneous
+    /// temperature exactly to the target, so [`Integrator::simulate_nvt`] should detect
+    /// equilibrium as soon as its averaging window has seen one post-rescale step.
+    #[test]
+    fn simulate_nvt_detects_equilibrium_once_rescaled() {
+        let amount_of_particles = 4;
+        let masses = Array::from_elem(amount_of_particles, 1.0);
+        let sigma = Array::ones((amount_of_particles, amount_of_particles));
+        let epsilon = Array::zeros((amount_of_particles, amount_of_particles));
+        let parameters = SystemParameters {
+            masses,
+            sigma,
+            epsilon,
+            box_dim: 1000.0,
+        };
+
+        let positions = Array::from_shape_fn((amount_of_particles, 3), |(i, d)| (i * 10 + d) as Float);
+        let velocities = array![
+            [1.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [-1.0, 0.0, 1.0],
+            [0.0, 0.0, -2.0],
+        ];
+        let target_temperature = 3.0;
+        let thermostat = VelocityRescaling { target_temperature };
+        let run_config = RunConfig {
+            time_step_size: 0.01,
+            max_time: 0.2,
+        };
+        let equilibration = EquilibrationConfig {
+            window: 1,
+            tolerance: 1e-3,
+        };
+
+        let mut verlet = Verlet {};
+        let result = verlet
+            .simulate_nvt(positions, velocities, run_config, &parameters, &thermostat, equilibration)
+            .unwrap();
+
+        assert!(
+            result.equilibrium_timestep.is_some(),
+            "a thermostat that snaps the temperature to target every step should equilibrate almost immediately"
+        );
+        let equilibrium_step = result.equilibrium_timestep.unwrap();
+        let equilibrium_temperature = temperature(
+            result.kinetic_energies[equilibrium_step],
+            amount_of_particles,
+        );
+        assert!(
+            (equilibrium_temperature - target_temperature).abs() < 1e-3,
+            "temperature at the detected equilibrium step ({equilibrium_temperature}) should match the target ({target_temperature})"
+        );
+    }
+
+    /// [`Berendsen`] only relaxes the temperature towards the target over its coupling time
+    /// instead of snapping to it, so a short coupling time relative to the step size should still
+    /// land the system within its equilibration tolerance after enough steps.
+    #[test]
+    fn simulate_nvt_berendsen_relaxes_towards_target() {
+        let amount_of_particles = 4;
+        let masses = Array::from_elem(amount_of_particles, 1.0);
+        let sigma = Array::ones((amount_of_particles, amount_of_particles));
+        let epsilon = Array::zeros((amount_of_particles, amount_of_particles));
+        let parameters = SystemParameters {
+            masses,
+            sigma,
+            epsilon,
+            box_dim: 1000.0,
+        };
+
+        let positions = Array::from_shape_fn((amount_of_particles, 3), |(i, d)| (i * 10 + d) as Float);
+        let velocities = array![
+            [1.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [-1.0, 0.0, 1.0],
+            [0.0, 0.0, -2.0],
+        ];
+        let target_temperature = 3.0;
+        let thermostat = Berendsen {
+            target_temperature,
+            coupling_time: 0.05,
+        };
+        let run_config = RunConfig {
+            time_step_size: 0.01,
+            max_time: 2.0,
+        };
+        let equilibration = EquilibrationConfig {
+            window: 5,
+            tolerance: 1e-2,
+        };
+
+        let mut verlet = Verlet {};
+        let result = verlet
+            .simulate_nvt(positions, velocities, run_config, &parameters, &thermostat, equilibration)
+            .unwrap();
+
+        assert!(
+            result.equilibrium_timestep.is_some(),
+            "Berendsen coupling should eventually relax the system within the equilibration tolerance"
+        );
+    }
+
+    /// Runs `amount_of_particles` non-interacting particles (ε = 0, so the O step is the only
+    /// thing moving their velocities) long enough for the Ornstein-Uhlenbeck bath to reach steady
+    /// state, then checks the pooled sample variance of every velocity component against the
+    /// equipartition prediction `Var(v) = T/m`.
+    #[test]
+    fn langevin_samples_the_target_temperature() {
+        let amount_of_particles = 8;
+        let masses = Array::from_elem(amount_of_particles, 1.0);
+        let sigma = Array::ones((amount_of_particles, amount_of_particles));
+        let epsilon = Array::zeros((amount_of_particles, amount_of_particles));
+        let parameters = SystemParameters {
+            masses,
+            sigma,
+            epsilon,
+            box_dim: 1000.0,
+        };
+
+        let positions = Array::from_shape_fn((amount_of_particles, 3), |(i, d)| (i * 10 + d) as Float);
+        let velocities = Array::zeros((amount_of_particles, 3));
+        let target_temperature = 2.0;
+        let mut langevin = Langevin::new(1.0, target_temperature, Some(7)).unwrap();
+        let run_config = RunConfig {
+            time_step_size: 0.01,
+            max_time: 50.0,
+        };
+        let result = langevin.simulate(positions, velocities, run_config, &parameters).unwrap();
+
+        // Discard the first half as burn-in, then pool every component of every particle at
+        // every remaining frame into one sample of the steady-state velocity distribution.
+        let burn_in = result.velocities.len() / 2;
+        let samples: Vec<Float> = result.velocities[burn_in..]
+            .iter()
+            .flat_map(|frame| frame.iter().copied())
+            .collect();
+        let mean = samples.iter().sum::<Float>() / samples.len() as Float;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<Float>() / samples.len() as Float;
+
+        assert!(
+            (variance - target_temperature).abs() < 0.2,
+            "sampled velocity variance {variance} should be close to T/m = {target_temperature}"
+        );
+    }
+
+    /// With `sample_interval` set, every recorded frame's time should be an exact multiple of
+    /// that interval, even though step-doubling is free to pick whatever `dt` the local error
+    /// estimate calls for in between.
+    #[test]
+    fn simulate_adaptive_frames_land_on_sample_interval() {
+        // No interaction (ε = 0), so the particles drift at constant velocity: step-doubling sees
+        // zero local error and keeps growing `dt` by `max_factor` every accepted step, quickly
+        // wanting to take strides far larger than `sample_interval`. Without the alignment cap
+        // those strides would jump clean over the requested sampling times.
+        let amount_of_particles = 2;
+        let masses = Array::from_elem(amount_of_particles, 1.0);
+        let sigma = Array::ones((amount_of_particles, amount_of_particles));
+        let epsilon = Array::zeros((amount_of_particles, amount_of_particles));
+        let parameters = SystemParameters {
+            masses,
+            sigma,
+            epsilon,
+            box_dim: 1000.0,
+        };
+
+        let positions = array![[8.0, 10.0, 10.0], [11.5, 10.0, 10.0]];
+        let velocities = array![[0.1, 0.0, 0.0], [-0.1, 0.0, 0.0]];
+        let run_config = RunConfig {
+            time_step_size: 0.01,
+            max_time: 1.0,
+        };
+        let sample_interval = 0.1;
+        let config = AdaptiveConfig {
+            sample_interval: Some(sample_interval),
+            ..AdaptiveConfig::default()
+        };
+
+        let mut verlet = Verlet {};
+        let result = verlet
+            .simulate_adaptive(positions, velocities, run_config, &parameters, config)
+            .unwrap();
+
+        let sample_count = (run_config.max_time / sample_interval).round() as usize;
+        for k in 1..sample_count {
+            let sample_time = k as Float * sample_interval;
+            let landed = result
+                .times
+                .iter()
+                .any(|&time| (time - sample_time).abs() < 1e-4);
+            assert!(
+                landed,
+                "no recorded frame at requested sampling time t={sample_time}, times were {:?}",
+                result.times
+            );
+        }
+    }
+}
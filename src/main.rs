@@ -1,30 +1,76 @@
-use integrator::{Integrator, Verlet, VerletCUDA};
+use std::fs::File;
+use std::io::BufWriter;
+
+use integrator::{Integrator, VerletNeighborList};
 use ndarray::{ArrayBase, Dim, OwnedRepr};
+use output::{MultiWriter, ObservablesWriter, XyzWriter};
+use species::{Species, SpeciesTable};
 
 mod initialisation;
 mod integrator;
+mod neighbors;
+mod output;
 mod physics;
+mod species;
+mod thermostat;
 
 type OneDee = ArrayBase<OwnedRepr<Float>, Dim<[usize; 1]>>;
 type TwoDee = ArrayBase<OwnedRepr<Float>, Dim<[usize; 2]>>;
 type ThreeDee = ArrayBase<OwnedRepr<Float>, Dim<[usize; 3]>>;
 type Error = Box<dyn std::error::Error>;
+
+/// Trades speed for the much better long-time energy conservation double precision gives in the
+/// LJ force evaluation. Enable with `--features f64`; `f32` remains the default.
+#[cfg(feature = "f64")]
+type Float = f64;
+#[cfg(not(feature = "f64"))]
 type Float = f32;
 
 const SEED: Option<u64> = Some(33);
-const BOX_SIZE: Float = 8.0;
-const NUMBER_OF_PARTICLES: usize = 500;
+// Scaled up from the old dense-path defaults (500 particles in an 8.0 box) at the same density,
+// large enough that `CellList` gets its required 3 cells per dimension against
+// `neighbors::CUTOFF_RADIUS + neighbors::SKIN_DISTANCE` and the neighbor list is actually doing
+// O(N) work instead of falling back to the dense candidate set.
+const BOX_SIZE: Float = 16.0;
+const NUMBER_OF_PARTICLES: usize = 4000;
 const TIME_STEP: Float = 0.005;
 const TOTAL_TIME: Float = 10.0;
+/// How many steps apart written trajectory/observable frames are, trading output size for
+/// temporal resolution.
+const SAMPLE_EVERY: usize = 10;
 
 fn main() -> Result<(), Error> {
     // parse configuration
+    let species_table = SpeciesTable::uniform(NUMBER_OF_PARTICLES, Species::UNIT);
+    let masses = species_table.masses();
+    let (sigma, epsilon) = species_table.mixing_matrices();
+    let parameters = integrator::SystemParameters {
+        masses,
+        sigma,
+        epsilon,
+        box_dim: BOX_SIZE,
+    };
+    let run_config = integrator::RunConfig {
+        time_step_size: TIME_STEP,
+        max_time: TOTAL_TIME,
+    };
+
     let positions = initialisation::initial_positions(NUMBER_OF_PARTICLES, BOX_SIZE)?;
-    let velocities = initialisation::initial_velocities(NUMBER_OF_PARTICLES, 0.01, SEED)?;
+    let velocities = initialisation::initial_velocities(&parameters.masses, 0.01, SEED)?;
+
+    let labels = vec!["Ar".to_string(); NUMBER_OF_PARTICLES];
+    let mut writer = MultiWriter::new(vec![
+        Box::new(XyzWriter::new(
+            BufWriter::new(File::create("trajectory.xyz")?),
+            labels,
+        )),
+        Box::new(ObservablesWriter::new(BufWriter::new(File::create(
+            "observables.csv",
+        )?))?),
+    ]);
 
-    let mut integrator = Verlet {};
-    let result = integrator.simulate(positions, velocities, TIME_STEP, TOTAL_TIME, BOX_SIZE)?;
-    println!("{:#?}", result);
+    let mut integrator = VerletNeighborList::new(neighbors::CUTOFF_RADIUS, neighbors::SKIN_DISTANCE);
+    integrator.simulate_streaming(positions, velocities, run_config, &parameters, SAMPLE_EVERY, &mut writer)?;
 
     Ok(())
 }
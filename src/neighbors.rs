@@ -0,0 +1,238 @@
+use crate::{Float, TwoDee, physics};
+
+/// Cutoff radius beyond which the Lennard-Jones interaction is truncated and shifted to zero.
+/// `2.5σ` is the standard choice for a monatomic LJ fluid.
+pub const CUTOFF_RADIUS: Float = 2.5;
+
+/// Extra skin distance added around [`CUTOFF_RADIUS`] when building the Verlet list, so the
+/// list stays valid for a handful of steps before particles can drift in or out of range.
+pub const SKIN_DISTANCE: Float = 0.3;
+
+/// Partitions the periodic box into cubic cells of side `>= min_cell_size`, bins particle
+/// indices into them, and can then produce every pair of particles that share a cell or occupy
+/// one of the 26 neighboring cells (applying periodic wraparound at the box edges). This turns
+/// the candidate-pair search from O(N²) into roughly O(N) for a roughly homogeneous density.
+///
+/// Falls back to treating the whole box as a single cell (i.e. every pair is a candidate) when
+/// the box is too small for 3 cells per dimension, since then a cell's 26 neighbors could alias
+/// onto itself and pairs would be double-counted under the normal stencil.
+pub struct CellList {
+    cells_per_dim: usize,
+    cells: Vec<Vec<usize>>,
+    dense_fallback: bool,
+}
+
+impl CellList {
+    /// Builds a cell list over `positions`. `min_cell_size` should be at least the cutoff (plus
+    /// skin, if used for a [`NeighborList`]) so that no interacting pair can be missed.
+    pub fn build(positions: &TwoDee, box_dim: Float, min_cell_size: Float) -> Self {
+        let cells_per_dim = (box_dim / min_cell_size).floor() as usize;
+        if cells_per_dim < 3 {
+            let all_particles = (0..positions.shape()[0]).collect();
+            return CellList {
+                cells_per_dim: 1,
+                cells: vec![all_particles],
+                dense_fallback: true,
+            };
+        }
+        let cell_size = box_dim / cells_per_dim as Float;
+        let mut cells = vec![Vec::new(); cells_per_dim.pow(3)];
+        for (i, position) in positions.outer_iter().enumerate() {
+            let index = Self::cell_of(position[0], position[1], position[2], cell_size, cells_per_dim, box_dim);
+            cells[index].push(i);
+        }
+        CellList {
+            cells_per_dim,
+            cells,
+            dense_fallback: false,
+        }
+    }
+
+    fn wrap(coord: Float, cell_size: Float, cells_per_dim: usize, box_dim: Float) -> usize {
+        let wrapped = coord.rem_euclid(box_dim);
+        ((wrapped / cell_size) as usize).min(cells_per_dim - 1)
+    }
+
+    fn cell_of(x: Float, y: Float, z: Float, cell_size: Float, cells_per_dim: usize, box_dim: Float) -> usize {
+        let cx = Self::wrap(x, cell_size, cells_per_dim, box_dim);
+        let cy = Self::wrap(y, cell_size, cells_per_dim, box_dim);
+        let cz = Self::wrap(z, cell_size, cells_per_dim, box_dim);
+        Self::flat_index(cx as isize, cy as isize, cz as isize, cells_per_dim as isize)
+    }
+
+    fn flat_index(cx: isize, cy: isize, cz: isize, n: isize) -> usize {
+        let wrap = |v: isize| v.rem_euclid(n);
+        ((wrap(cx) * n + wrap(cy)) * n + wrap(cz)) as usize
+    }
+
+    /// Every unordered pair `(i, j)` with `i < j` whose cells are the same or adjacent (with
+    /// periodic wraparound), i.e. the candidate set that a Lennard-Jones cutoff evaluation needs
+    /// to check instead of all `N(N-1)/2` pairs.
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        if self.dense_fallback {
+            Self::push_cell_pair(&self.cells[0], &self.cells[0], true, &mut pairs);
+            return pairs;
+        }
+        let n = self.cells_per_dim as isize;
+        for cx in 0..n {
+            for cy in 0..n {
+                for cz in 0..n {
+                    let this_index = Self::flat_index(cx, cy, cz, n);
+                    for dx in -1..=1 {
+                        for dy in -1..=1 {
+                            for dz in -1..=1 {
+                                let other_index = Self::flat_index(cx + dx, cy + dy, cz + dz, n);
+                                if other_index < this_index {
+                                    continue;
+                                }
+                                Self::push_cell_pair(&self.cells[this_index], &self.cells[other_index], this_index == other_index, &mut pairs);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    fn push_cell_pair(this_cell: &[usize], other_cell: &[usize], same_cell: bool, pairs: &mut Vec<(usize, usize)>) {
+        if same_cell {
+            for (offset, &i) in this_cell.iter().enumerate() {
+                for &j in &this_cell[offset + 1..] {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        } else {
+            for &i in this_cell {
+                for &j in other_cell {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+    }
+}
+
+/// A Verlet neighbor list: the candidate pairs within `cutoff + skin` of each other, kept valid
+/// across several integration steps by only rebuilding once a particle could plausibly have
+/// entered or left the cutoff shell.
+pub struct NeighborList {
+    pub pairs: Vec<(usize, usize)>,
+    reference_positions: TwoDee,
+    skin: Float,
+}
+
+impl NeighborList {
+    /// Rebuilds the list from scratch: bins particles with a [`CellList`] sized to
+    /// `cutoff + skin`, then keeps only the candidates actually within that range.
+    pub fn build(positions: &TwoDee, box_dim: Float, cutoff: Float, skin: Float) -> Self {
+        let cell_list = CellList::build(positions, box_dim, cutoff + skin);
+        let candidates = cell_list.candidate_pairs();
+        let candidate_distances = physics::atomic_distances_pairs(positions, box_dim, &candidates);
+        let shell = cutoff + skin;
+        let pairs = candidates
+            .into_iter()
+            .zip(candidate_distances.distances)
+            .filter(|&(_, distance)| distance <= shell)
+            .map(|(pair, _)| pair)
+            .collect();
+        NeighborList {
+            pairs,
+            reference_positions: positions.clone(),
+            skin,
+        }
+    }
+
+    /// True once the largest displacement of any particle since the list was built exceeds
+    /// `skin / 2`, meaning a pair could have crossed into or out of the cutoff shell unnoticed
+    /// and the list must be rebuilt before the next force evaluation.
+    pub fn needs_rebuild(&self, positions: &TwoDee, box_dim: Float) -> bool {
+        self.reference_positions
+            .outer_iter()
+            .zip(positions.outer_iter())
+            .map(|(old, new)| {
+                (0..3)
+                    .map(|d| physics::minimum_image_component(new[d] - old[d], box_dim).abs())
+                    .fold(0.0, Float::max)
+            })
+            .fold(0.0, Float::max)
+            > self.skin / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::array;
+
+    use crate::physics::{atomic_distances, atomic_distances_pairs, lj_force, lj_force_pairs};
+
+    use super::{CellList, NeighborList};
+
+    #[test]
+    fn neighbor_list_matches_dense_forces() {
+        let positions = array![
+            [1.0, 2.0, 3.0],
+            [0.0, 2.0, 2.0],
+            [3.0, 3.0, 3.0],
+            [5.0, 1.0, 4.0],
+        ];
+        // Box large enough that `CellList` still gets its required 3 cells per dimension even
+        // with a cutoff + skin that spans every pair below.
+        let box_dim = 40.0;
+        let cutoff = 10.0;
+        let skin = 1.0;
+
+        let list = NeighborList::build(&positions, box_dim, cutoff, skin);
+        let amount_of_particles = positions.shape()[0];
+        let expected_pair_count = amount_of_particles * (amount_of_particles - 1) / 2;
+        assert_eq!(
+            list.pairs.len(),
+            expected_pair_count,
+            "cutoff covers the whole box, so every pair should be a neighbor"
+        );
+
+        let sigma = ndarray::Array::ones((amount_of_particles, amount_of_particles));
+        let epsilon = ndarray::Array::ones((amount_of_particles, amount_of_particles));
+
+        let pair_distances = atomic_distances_pairs(&positions, box_dim, &list.pairs);
+        let (sparse_forces, _potential, _virial) =
+            lj_force_pairs(amount_of_particles, &list.pairs, &pair_distances, &sigma, &epsilon, cutoff);
+
+        let (relative_positions, distances) = atomic_distances(&positions, box_dim).unwrap();
+        let (_magnitudes, dense_forces) = lj_force(relative_positions, &distances, &sigma, &epsilon);
+
+        for i in 0..amount_of_particles {
+            for d in 0..3 {
+                assert!(
+                    (sparse_forces[[i, d]] - dense_forces[[i, d]]).abs() < 1e-4,
+                    "particle {i}, component {d}: sparse {} vs dense {}",
+                    sparse_forces[[i, d]],
+                    dense_forces[[i, d]]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cell_list_falls_back_to_dense_when_box_is_too_small() {
+        let positions = array![
+            [1.0, 2.0, 3.0],
+            [0.0, 2.0, 2.0],
+            [3.0, 3.0, 3.0],
+            [5.0, 1.0, 4.0],
+        ];
+        // `box_dim / min_cell_size` rounds down to 2 cells per dimension here, below the 3
+        // the normal stencil needs to avoid aliasing a cell onto itself.
+        let box_dim = 8.0;
+        let min_cell_size = 2.8;
+
+        let list = CellList::build(&positions, box_dim, min_cell_size);
+        let amount_of_particles = positions.shape()[0];
+        let expected_pair_count = amount_of_particles * (amount_of_particles - 1) / 2;
+        assert_eq!(
+            list.candidate_pairs().len(),
+            expected_pair_count,
+            "falling back to a single cell should still consider every pair a candidate"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::io::Write;
+
+use crate::{Error, Float, TwoDee};
+
+/// A destination for simulation output that consumes one frame at a time instead of requiring
+/// every step's positions and velocities to be kept in memory, so a run can go on far longer
+/// than would fit in RAM (or in an in-memory [`crate::integrator::IntegrationResult`]).
+pub trait TrajectoryWriter {
+    #[allow(clippy::too_many_arguments)]
+    fn write_frame(
+        &mut self,
+        step: usize,
+        time: Float,
+        box_dim: Float,
+        positions: &TwoDee,
+        velocities: &TwoDee,
+        kinetic_energy: Float,
+        potential_energy: Float,
+        virial: Float,
+        temperature: Float,
+    ) -> Result<(), Error>;
+}
+
+/// Writes each frame as an extended-XYZ trajectory frame: a particle count, a comment line
+/// carrying the box size and the step's observables, then one `type x y z vx vy vz` line per
+/// particle. This is the format standard MD visualization/analysis tools (e.g. OVITO, VMD)
+/// expect.
+pub struct XyzWriter<W: Write> {
+    out: W,
+    labels: Vec<String>,
+}
+
+impl<W: Write> XyzWriter<W> {
+    /// `labels` gives each particle's type column, e.g. an element symbol per species.
+    pub fn new(out: W, labels: Vec<String>) -> Self {
+        XyzWriter { out, labels }
+    }
+}
+
+impl<W: Write> TrajectoryWriter for XyzWriter<W> {
+    fn write_frame(
+        &mut self,
+        step: usize,
+        time: Float,
+        box_dim: Float,
+        positions: &TwoDee,
+        velocities: &TwoDee,
+        kinetic_energy: Float,
+        potential_energy: Float,
+        virial: Float,
+        temperature: Float,
+    ) -> Result<(), Error> {
+        writeln!(self.out, "{}", positions.shape()[0])?;
+        writeln!(
+            self.out,
+            "Lattice=\"{box_dim} 0.0 0.0 0.0 {box_dim} 0.0 0.0 0.0 {box_dim}\" Properties=species:S:1:pos:R:3:velo:R:3 step={step} time={time:.6} ke={kinetic_energy:.6} pe={potential_energy:.6} virial={virial:.6} temperature={temperature:.6}"
+        )?;
+        for (i, position) in positions.outer_iter().enumerate() {
+            let velocity = velocities.row(i);
+            writeln!(
+                self.out,
+                "{} {:.6} {:.6} {:.6} {:.6} {:.6} {:.6}",
+                self.labels[i], position[0], position[1], position[2], velocity[0], velocity[1], velocity[2]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the scalar observables of each frame as a CSV time series, independent of the (much
+/// larger) per-particle trajectory.
+pub struct ObservablesWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> ObservablesWriter<W> {
+    pub fn new(mut out: W) -> Result<Self, Error> {
+        writeln!(out, "step,time,kinetic_energy,potential_energy,virial,temperature,pressure")?;
+        Ok(ObservablesWriter { out })
+    }
+}
+
+impl<W: Write> TrajectoryWriter for ObservablesWriter<W> {
+    fn write_frame(
+        &mut self,
+        step: usize,
+        time: Float,
+        box_dim: Float,
+        positions: &TwoDee,
+        _velocities: &TwoDee,
+        kinetic_energy: Float,
+        potential_energy: Float,
+        virial: Float,
+        temperature: Float,
+    ) -> Result<(), Error> {
+        let pressure = virial_pressure(positions.shape()[0], box_dim, temperature, virial);
+        writeln!(
+            self.out,
+            "{step},{time:.6},{kinetic_energy:.6},{potential_energy:.6},{virial:.6},{temperature:.6},{pressure:.6}"
+        )?;
+        Ok(())
+    }
+}
+
+/// The virial-theorem estimate of pressure, `P = (N·k_B·T + virial) / (3V)` (with `k_B = 1` in
+/// this crate's reduced units), for a cubic box of side `box_dim`.
+fn virial_pressure(amount_of_particles: usize, box_dim: Float, temperature: Float, virial: Float) -> Float {
+    let volume = box_dim.powi(3);
+    (amount_of_particles as Float * temperature + virial) / (3.0 * volume)
+}
+
+/// Fans a single frame out to every writer in `writers`, so a trajectory file and an
+/// observables log can be kept in sync from one [`crate::integrator::Integrator::simulate_streaming`]
+/// call.
+pub struct MultiWriter {
+    writers: Vec<Box<dyn TrajectoryWriter>>,
+}
+
+impl MultiWriter {
+    pub fn new(writers: Vec<Box<dyn TrajectoryWriter>>) -> Self {
+        MultiWriter { writers }
+    }
+}
+
+impl TrajectoryWriter for MultiWriter {
+    fn write_frame(
+        &mut self,
+        step: usize,
+        time: Float,
+        box_dim: Float,
+        positions: &TwoDee,
+        velocities: &TwoDee,
+        kinetic_energy: Float,
+        potential_energy: Float,
+        virial: Float,
+        temperature: Float,
+    ) -> Result<(), Error> {
+        for writer in &mut self.writers {
+            writer.write_frame(
+                step,
+                time,
+                box_dim,
+                positions,
+                velocities,
+                kinetic_energy,
+                potential_energy,
+                virial,
+                temperature,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn xyz_frame_declares_properties_for_velocities() {
+        let positions = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let velocities = array![[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]];
+        let mut out = Vec::new();
+        {
+            let mut writer = XyzWriter::new(&mut out, vec!["Ar".to_string(), "Ar".to_string()]);
+            writer
+                .write_frame(0, 0.0, 8.0, &positions, &velocities, 1.0, 2.0, 3.0, 4.0)
+                .unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("2"));
+        let comment = lines.next().unwrap();
+        assert!(
+            comment.contains("Properties=species:S:1:pos:R:3:velo:R:3"),
+            "comment line should declare the velocity columns so readers don't drop them: {comment}"
+        );
+        assert_eq!(lines.next(), Some("Ar 1.000000 2.000000 3.000000 0.100000 0.200000 0.300000"));
+    }
+
+    #[test]
+    fn observables_writer_reports_virial_pressure() {
+        let positions = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let velocities = array![[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]];
+        let mut out = Vec::new();
+        {
+            let mut writer = ObservablesWriter::new(&mut out).unwrap();
+            writer
+                .write_frame(0, 0.0, 8.0, &positions, &velocities, 1.0, 2.0, 3.0, 4.0)
+                .unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("step,time,kinetic_energy,potential_energy,virial,temperature,pressure")
+        );
+        // pressure = (N*T + virial) / (3*box_dim^3) = (2*4.0 + 3.0) / (3*512.0) = 11.0 / 1536.0
+        assert_eq!(lines.next(), Some("0,0.000000,1.000000,2.000000,3.000000,4.000000,0.007161"));
+    }
+}
@@ -1,11 +1,20 @@
-use std::f64::INFINITY;
-
 use ndarray::{AssignElem, Axis, stack};
 
-use crate::{Error, Float, ThreeDee, TwoDee};
+use crate::{Error, Float, OneDee, ThreeDee, TwoDee};
 
-pub fn lj_force(relative_positions: ThreeDee, distances: &TwoDee) -> (TwoDee, TwoDee) {
-    let force_magnitude = 24.0 * distances.powi(-7) - 48.0 * distances.powi(-13);
+/// Lennard-Jones force between every pair of particles, using the per-pair `(σ_ij, ε_ij)`
+/// matrices produced by [`crate::species::SpeciesTable`] (Lorentz–Berthelot mixing of each
+/// particle's species parameters) instead of assuming σ = ε = 1 for a single species.
+pub fn lj_force(
+    relative_positions: ThreeDee,
+    distances: &TwoDee,
+    sigma: &TwoDee,
+    epsilon: &TwoDee,
+) -> (TwoDee, TwoDee) {
+    let sigma6 = sigma.mapv(|s| s.powi(6));
+    let sigma12 = sigma.mapv(|s| s.powi(12));
+    let force_magnitude =
+        24.0 * epsilon * &sigma6 * distances.powi(-7) - 48.0 * epsilon * &sigma12 * distances.powi(-13);
     assert!(!force_magnitude.is_any_nan());
     let force_direction = relative_positions / distances.view().insert_axis(Axis(2));
     assert!(!force_direction.is_any_nan());
@@ -39,7 +48,7 @@ pub fn atomic_distances(
     let relative_positions = stack!(Axis(2), dists[0], dists[1], dists[2]);
     let mut distances =
         (&dists[0] * &dists[0] + &dists[1] * &dists[1] + &dists[2] * &dists[2]).sqrt();
-    distances.diag_mut().fill(INFINITY as Float);
+    distances.diag_mut().fill(Float::INFINITY);
     assert!(!relative_positions.is_any_nan());
     assert_eq!(relative_positions.shape()[0], amount_of_particles);
     assert_eq!(relative_positions.shape()[1], amount_of_particles);
@@ -50,12 +59,14 @@ pub fn atomic_distances(
     Ok((relative_positions, distances))
 }
 
-pub fn kinetic_energy(velocities: &TwoDee) -> Float {
-    0.5 * velocities.powi(2).sum()
+pub fn kinetic_energy(velocities: &TwoDee, masses: &OneDee) -> Float {
+    0.5 * (velocities.powi(2).sum_axis(Axis(1)) * masses).sum()
 }
 
-pub fn potential_energy(distances: &TwoDee) -> Float {
-    let individual = 4.0 * (distances.powi(-12) - distances.powi(-6));
+pub fn potential_energy(distances: &TwoDee, sigma: &TwoDee, epsilon: &TwoDee) -> Float {
+    let sigma6 = sigma.mapv(|s| s.powi(6));
+    let sigma12 = sigma.mapv(|s| s.powi(12));
+    let individual = 4.0 * epsilon * (sigma12 * distances.powi(-12) - sigma6 * distances.powi(-6));
     0.5 * individual.sum()
 }
 
@@ -63,6 +74,96 @@ pub fn temperature(kinetic_energy: Float, amount_of_particles: usize) -> Float {
     2.0 * kinetic_energy / (3.0 * (amount_of_particles - 1) as Float)
 }
 
+/// Wraps every coordinate back into `[0, box_dim)` under periodic boundary conditions. Plain
+/// velocity Verlet never needs this, since [`atomic_distances`] already applies the minimum-image
+/// convention to relative positions regardless of how far positions have drifted, but a diffusive
+/// integrator like [`crate::integrator::Langevin`] drifts positions directly and should keep them
+/// from growing without bound over a long run.
+pub fn wrap_into_box(positions: TwoDee, box_dim: Float) -> TwoDee {
+    positions.mapv_into(|x| x.rem_euclid(box_dim))
+}
+
+/// Wraps a single coordinate difference into `(-box_dim/2, box_dim/2]` under the minimum-image
+/// convention, the same rule [`atomic_distances`] applies to the full relative-position tensor.
+pub fn minimum_image_component(difference: Float, box_dim: Float) -> Float {
+    (difference + box_dim * 0.5) % box_dim - box_dim * 0.5
+}
+
+/// The relative position and distance of each pair in `pairs`, applying the minimum-image
+/// convention exactly like [`atomic_distances`] but only for the requested sparse pair list
+/// (e.g. a [`crate::neighbors::NeighborList`]) instead of the full N×N dense matrix.
+pub struct PairDistances {
+    pub relative_positions: Vec<[Float; 3]>,
+    pub distances: Vec<Float>,
+}
+
+pub fn atomic_distances_pairs(
+    positions: &TwoDee,
+    box_dim: Float,
+    pairs: &[(usize, usize)],
+) -> PairDistances {
+    let mut relative_positions = Vec::with_capacity(pairs.len());
+    let mut distances = Vec::with_capacity(pairs.len());
+    for &(i, j) in pairs {
+        let pi = positions.row(i);
+        let pj = positions.row(j);
+        let relative = [
+            minimum_image_component(pi[0] - pj[0], box_dim),
+            minimum_image_component(pi[1] - pj[1], box_dim),
+            minimum_image_component(pi[2] - pj[2], box_dim),
+        ];
+        let distance = (relative[0] * relative[0] + relative[1] * relative[1] + relative[2] * relative[2]).sqrt();
+        relative_positions.push(relative);
+        distances.push(distance);
+    }
+    PairDistances {
+        relative_positions,
+        distances,
+    }
+}
+
+/// Truncated-and-shifted Lennard-Jones force and potential energy over a sparse pair list, using
+/// the same per-pair `(σ_ij, ε_ij)` mixing matrices [`lj_force`] does, turning the per-step cost
+/// into roughly O(N) instead of the O(N²) dense evaluation. Pairs farther apart than `cutoff` are
+/// ignored, and each pair's potential is shifted by its value at `cutoff` so it goes continuously
+/// to zero there instead of jumping.
+pub fn lj_force_pairs(
+    amount_of_particles: usize,
+    pairs: &[(usize, usize)],
+    pair_distances: &PairDistances,
+    sigma: &TwoDee,
+    epsilon: &TwoDee,
+    cutoff: Float,
+) -> (TwoDee, Float, Float) {
+    let mut forces = TwoDee::zeros((amount_of_particles, 3));
+    let mut potential = 0.0;
+    let mut virial = 0.0;
+    for (&(i, j), (&relative, &distance)) in pairs.iter().zip(
+        pair_distances
+            .relative_positions
+            .iter()
+            .zip(pair_distances.distances.iter()),
+    ) {
+        if distance > cutoff {
+            continue;
+        }
+        let sigma6 = sigma[[i, j]].powi(6);
+        let sigma12 = sigma[[i, j]].powi(12);
+        let eps = epsilon[[i, j]];
+        let shift = 4.0 * eps * (sigma12 * cutoff.powi(-12) - sigma6 * cutoff.powi(-6));
+        let force_magnitude =
+            24.0 * eps * sigma6 * distance.powi(-7) - 48.0 * eps * sigma12 * distance.powi(-13);
+        for d in 0..3 {
+            let component = -force_magnitude * relative[d] / distance;
+            forces[[i, d]] += component;
+            forces[[j, d]] -= component;
+        }
+        potential += 4.0 * eps * (sigma12 * distance.powi(-12) - sigma6 * distance.powi(-6)) - shift;
+        virial += distance * force_magnitude;
+    }
+    (forces, potential, virial)
+}
+
 #[cfg(test)]
 mod test {
     use std::f64::{INFINITY, consts::SQRT_2};
@@ -71,7 +172,7 @@ mod test {
 
     use crate::Float;
 
-    use super::{atomic_distances, lj_force};
+    use super::{atomic_distances, atomic_distances_pairs, lj_force, lj_force_pairs, potential_energy};
 
     #[test]
     fn test_relative_positions() {
@@ -96,11 +197,55 @@ mod test {
 
     #[test]
     fn test_force_direction() {
+        // Particles one unit apart along x, well inside σ = 1's repulsive core (the LJ minimum
+        // sits at d = 2^(1/6)σ ≈ 1.122), so each should be pushed away from the other along x.
         let positions = array![[1.0, 2.0, 3.0], [0.0, 2.0, 3.0]];
         let (relative_positions, distances) = atomic_distances(&positions, 50.0).unwrap();
-        let (magnitude, force) = lj_force(relative_positions, &distances);
-        println!("{:#?}", force);
-        println!("{:#?}", magnitude);
-        todo!()
+        let sigma = Array::ones((2, 2));
+        let epsilon = Array::ones((2, 2));
+        let (magnitude, force) = lj_force(relative_positions, &distances, &sigma, &epsilon);
+        assert_eq!(magnitude, array![[0.0, -24.0], [-24.0, 0.0]]);
+        assert_eq!(force, array![[24.0, 0.0, 0.0], [-24.0, 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn lj_force_pairs_honors_per_pair_mixing_matrices() {
+        let positions = array![[1.0, 2.0, 3.0], [0.0, 2.0, 2.0], [3.0, 3.0, 3.0]];
+        let box_dim = 40.0;
+        // Non-uniform, so this would fail if `lj_force_pairs` ever went back to assuming σ = ε = 1.
+        let sigma = array![[1.0, 1.2, 0.9], [1.2, 1.0, 1.1], [0.9, 1.1, 1.0]];
+        let epsilon = array![[1.0, 0.8, 1.3], [0.8, 1.0, 0.7], [1.3, 0.7, 1.0]];
+        let amount_of_particles = positions.shape()[0];
+        let pairs = vec![(0, 1), (0, 2), (1, 2)];
+
+        let pair_distances = atomic_distances_pairs(&positions, box_dim, &pairs);
+        let (sparse_forces, sparse_potential, sparse_virial) = lj_force_pairs(
+            amount_of_particles,
+            &pairs,
+            &pair_distances,
+            &sigma,
+            &epsilon,
+            box_dim,
+        );
+
+        let (relative_positions, distances) = atomic_distances(&positions, box_dim).unwrap();
+        let (_magnitudes, dense_forces) = lj_force(relative_positions, &distances, &sigma, &epsilon);
+        let dense_potential = potential_energy(&distances, &sigma, &epsilon);
+
+        for i in 0..amount_of_particles {
+            for d in 0..3 {
+                assert!(
+                    (sparse_forces[[i, d]] - dense_forces[[i, d]]).abs() < 1e-4,
+                    "particle {i}, component {d}: sparse {} vs dense {}",
+                    sparse_forces[[i, d]],
+                    dense_forces[[i, d]]
+                );
+            }
+        }
+        assert!(
+            (sparse_potential - dense_potential).abs() < 1e-4,
+            "sparse potential {sparse_potential} vs dense {dense_potential}"
+        );
+        assert!(sparse_virial.is_finite());
     }
 }
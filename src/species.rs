@@ -0,0 +1,101 @@
+use crate::{Float, OneDee, TwoDee};
+
+/// A single particle species: its mass and unmixed Lennard-Jones parameters `(σ, ε)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Species {
+    pub mass: Float,
+    pub sigma: Float,
+    pub epsilon: Float,
+}
+
+impl Species {
+    /// The unit-mass, σ = ε = 1 species.
+    pub const UNIT: Species = Species {
+        mass: 1.0,
+        sigma: 1.0,
+        epsilon: 1.0,
+    };
+}
+
+/// Assigns each particle one of a small table of [`Species`], and derives the per-particle mass
+/// array and the pairwise `(σ_ij, ε_ij)` matrices that [`crate::physics::lj_force`] and
+/// [`crate::physics::potential_energy`] need, mixed according to the Lorentz–Berthelot rules
+/// `σ_ij = (σ_i + σ_j)/2`, `ε_ij = sqrt(ε_i · ε_j)`.
+pub struct SpeciesTable {
+    species: Vec<Species>,
+    assignment: Vec<usize>,
+}
+
+impl SpeciesTable {
+    pub fn new(species: Vec<Species>, assignment: Vec<usize>) -> Self {
+        assert!(
+            assignment.iter().all(|&index| index < species.len()),
+            "particle assigned to a species outside the table"
+        );
+        SpeciesTable { species, assignment }
+    }
+
+    /// `amount_of_particles` copies of a single species, recovering the original monatomic
+    /// unit-mass fluid.
+    pub fn uniform(amount_of_particles: usize, species: Species) -> Self {
+        SpeciesTable {
+            species: vec![species],
+            assignment: vec![0; amount_of_particles],
+        }
+    }
+
+    pub fn masses(&self) -> OneDee {
+        OneDee::from_iter(self.assignment.iter().map(|&index| self.species[index].mass))
+    }
+
+    /// The mixed `σ_ij` and `ε_ij` matrices for every particle pair (the diagonal is unused,
+    /// since [`crate::physics::atomic_distances`] sets self-distances to infinity).
+    pub fn mixing_matrices(&self) -> (TwoDee, TwoDee) {
+        let n = self.assignment.len();
+        let mut sigma = TwoDee::zeros((n, n));
+        let mut epsilon = TwoDee::zeros((n, n));
+        for i in 0..n {
+            let species_i = self.species[self.assignment[i]];
+            for j in 0..n {
+                let species_j = self.species[self.assignment[j]];
+                sigma[[i, j]] = (species_i.sigma + species_j.sigma) * 0.5;
+                epsilon[[i, j]] = (species_i.epsilon * species_j.epsilon).sqrt();
+            }
+        }
+        (sigma, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn mixing_matrices_apply_lorentz_berthelot_rules_across_species() {
+        let light = Species {
+            mass: 1.0,
+            sigma: 1.0,
+            epsilon: 1.0,
+        };
+        let heavy = Species {
+            mass: 2.0,
+            sigma: 2.0,
+            epsilon: 4.0,
+        };
+        let table = SpeciesTable::new(vec![light, heavy], vec![0, 1, 0]);
+
+        assert_eq!(table.masses(), array![1.0, 2.0, 1.0]);
+
+        let (sigma, epsilon) = table.mixing_matrices();
+        assert_eq!(
+            sigma,
+            array![[1.0, 1.5, 1.0], [1.5, 2.0, 1.5], [1.0, 1.5, 1.0]]
+        );
+        assert_eq!(
+            epsilon,
+            array![[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Float, TwoDee};
+
+/// A velocity thermostat coupling the system to a heat bath at a target temperature, used during
+/// the equilibration phase of [`crate::integrator::Integrator::simulate_nvt`] in place of the
+/// constant-energy (NVE) dynamics plain `simulate` performs.
+pub trait Thermostat {
+    /// The heat-bath temperature this thermostat is coupling the system towards.
+    fn target_temperature(&self) -> Float;
+    /// Rescales `velocities` in place given the instantaneous temperature measured this step.
+    fn apply(&self, velocities: &mut TwoDee, current_temperature: Float, time_step_size: Float);
+}
+
+/// Berendsen weak-coupling thermostat: each step scales all velocities by
+/// `λ = sqrt(1 + (dt/τ)(T0/T − 1))`, relaxing the instantaneous temperature `T` towards the
+/// target `T0` over the coupling timescale `τ`.
+pub struct Berendsen {
+    pub target_temperature: Float,
+    pub coupling_time: Float,
+}
+
+impl Thermostat for Berendsen {
+    fn target_temperature(&self) -> Float {
+        self.target_temperature
+    }
+
+    fn apply(&self, velocities: &mut TwoDee, current_temperature: Float, time_step_size: Float) {
+        // T0/T blows up to infinity at T = 0 (e.g. velocities initialized to zero); there's
+        // nothing to rescale yet, so leave the (zero) velocities alone and let the dynamics give
+        // the system some kinetic energy to couple to before the next step.
+        if current_temperature <= 0.0 {
+            return;
+        }
+        let lambda = (1.0
+            + (time_step_size / self.coupling_time) * (self.target_temperature / current_temperature - 1.0))
+            .sqrt();
+        *velocities *= lambda;
+    }
+}
+
+/// Direct velocity rescaling: snaps the instantaneous temperature straight to `T0` every step.
+/// This is the `τ = dt` special case of [`Berendsen`], since there `λ = sqrt(T0/T)`.
+pub struct VelocityRescaling {
+    pub target_temperature: Float,
+}
+
+impl Thermostat for VelocityRescaling {
+    fn target_temperature(&self) -> Float {
+        self.target_temperature
+    }
+
+    fn apply(&self, velocities: &mut TwoDee, current_temperature: Float, _time_step_size: Float) {
+        // T0/T blows up to infinity at T = 0 (e.g. velocities initialized to zero); there's
+        // nothing to rescale yet, so leave the (zero) velocities alone and let the dynamics give
+        // the system some kinetic energy to couple to before the next step.
+        if current_temperature <= 0.0 {
+            return;
+        }
+        let lambda = (self.target_temperature / current_temperature).sqrt();
+        *velocities *= lambda;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn berendsen_leaves_zero_velocities_alone() {
+        let thermostat = Berendsen {
+            target_temperature: 2.0,
+            coupling_time: 0.1,
+        };
+        let mut velocities = array![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]];
+        thermostat.apply(&mut velocities, 0.0, 0.01);
+        assert!(!velocities.iter().any(|v| !v.is_finite()));
+        assert_eq!(velocities, array![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn velocity_rescaling_leaves_zero_velocities_alone() {
+        let thermostat = VelocityRescaling {
+            target_temperature: 2.0,
+        };
+        let mut velocities = array![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]];
+        thermostat.apply(&mut velocities, 0.0, 0.01);
+        assert!(!velocities.iter().any(|v| !v.is_finite()));
+        assert_eq!(velocities, array![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+    }
+}